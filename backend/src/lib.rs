@@ -2,6 +2,7 @@ use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use wasm_bindgen::JsValue;
 use worker::*;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -9,41 +10,71 @@ type HmacSha256 = Hmac<Sha256>;
 const MAX_CONTENT_LENGTH: usize = 4096;
 const KV_BINDING: &str = "COUNCIL_KV";
 const SECRET_BINDING: &str = "HMAC_SECRET";
+const ORGANIZER_SECRET_BINDING: &str = "ORGANIZER_SECRET";
+const ROOM_DO_BINDING: &str = "ROOM_DO";
 const MAX_MSG_FETCH: usize = 200;
+const DEFAULT_CONTEXT_LIMIT: usize = 20;
+const PRESENCE_ONLINE_WINDOW_MS: u64 = 60_000;
+const MAX_REASON_LENGTH: usize = 1024;
+const MIN_SEVERITY: u8 = 1;
+const MAX_SEVERITY: u8 = 5;
 
 // ==================== Country ====================
 
+const COUNTRIES: [&str; 7] = [
+    "england", "france", "germany", "italy", "austria", "russia", "turkey",
+];
+
 fn valid_country(s: &str) -> bool {
-    matches!(
-        s,
-        "england" | "france" | "germany" | "italy" | "austria" | "russia" | "turkey"
-    )
+    COUNTRIES.contains(&s)
 }
 
 // ==================== Token ====================
-// Format: {room_id}|{country}|{hmac_hex}
+// Format: {room_id}|{country}|{is_organizer}|{hmac_hex}
 // Splitting from the right handles room_ids that might contain '|'.
 
-fn compute_hmac_hex(secret: &str, room_id: &str, country: &str) -> String {
+fn compute_hmac_hex(secret: &str, room_id: &str, country: &str, is_organizer: bool) -> String {
     let mut mac =
         HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
-    mac.update(format!("{}:{}", room_id, country).as_bytes());
+    mac.update(format!("{}:{}:{}", room_id, country, is_organizer).as_bytes());
     hex::encode(mac.finalize().into_bytes())
 }
 
-fn make_token(secret: &str, room_id: &str, country: &str) -> String {
-    format!("{}|{}|{}", room_id, country, compute_hmac_hex(secret, room_id, country))
+fn make_token(secret: &str, room_id: &str, country: &str, is_organizer: bool) -> String {
+    format!(
+        "{}|{}|{}|{}",
+        room_id,
+        country,
+        if is_organizer { "1" } else { "0" },
+        compute_hmac_hex(secret, room_id, country, is_organizer)
+    )
+}
+
+// Signs an arbitrary payload (e.g. a webhook body) with a caller-supplied
+// secret, independent of the room/country-scoped token HMAC above.
+fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
 }
 
 struct Claims {
     room_id: String,
     country: String,
+    // Set only when `POST /api/auth` was called with the correct
+    // ORGANIZER_SECRET binding value. Signed into the token like everything
+    // else, so a caller can't self-escalate by editing the token.
+    is_organizer: bool,
 }
 
 fn verify_token(secret: &str, token: &str) -> Option<Claims> {
     let last = token.rfind('|')?;
     let hmac_part = &token[last + 1..];
     let rest = &token[..last];
+    let organizer_mid = rest.rfind('|')?;
+    let organizer_part = &rest[organizer_mid + 1..];
+    let rest = &rest[..organizer_mid];
     let mid = rest.rfind('|')?;
     let country_part = &rest[mid + 1..];
     let room_id_part = &rest[..mid];
@@ -51,12 +82,14 @@ fn verify_token(secret: &str, token: &str) -> Option<Claims> {
     if !valid_country(country_part) {
         return None;
     }
-    if compute_hmac_hex(secret, room_id_part, country_part) != hmac_part {
+    let is_organizer = organizer_part == "1";
+    if compute_hmac_hex(secret, room_id_part, country_part, is_organizer) != hmac_part {
         return None;
     }
     Some(Claims {
         room_id: room_id_part.to_string(),
         country: country_part.to_string(),
+        is_organizer,
     })
 }
 
@@ -94,6 +127,213 @@ async fn kv_put<T: Serialize>(kv: &kv::KvStore, key: &str, value: &T) -> Result<
         .map_err(|e| Error::RustError(e.to_string()))
 }
 
+// Extracts the numeric seq out of a `{prefix}{seq:020}:{message_id}` key.
+// Comparing the parsed number (rather than comparing key strings against a
+// bare `{seq:020}:` boundary) avoids the prefix trap where a string that is
+// a strict prefix of another always sorts before it.
+fn seq_from_key(prefix: &str, key: &str) -> Option<u64> {
+    key.strip_prefix(prefix)?.split(':').next()?.parse().ok()
+}
+
+// ==================== Room Durable Object ====================
+//
+// One instance per room_id. Its single-threaded `fetch` is the
+// serialization point for decisions that KV's eventually-consistent,
+// non-atomic check-and-set can't make safely: claiming a seat, creating a
+// conversation for the first time (including appending it to the room's
+// shared conversation list), and allocating a conversation's next message
+// sequence number. Durable Object storage owns the room's conversation
+// list as the source of truth; KV remains the long-term store for the
+// seat flags, conversation metadata mirror, and message bodies.
+
+#[durable_object]
+pub struct RoomDurableObject {
+    state: State,
+}
+
+#[durable_object]
+impl DurableObject for RoomDurableObject {
+    fn new(state: State, _env: Env) -> Self {
+        Self { state }
+    }
+
+    async fn fetch(&mut self, mut req: Request) -> Result<Response> {
+        match req.path().as_str() {
+            "/claim_seat" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    country: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    claimed: bool,
+                }
+
+                let body: Body = req.json().await?;
+                let storage = self.state.storage();
+                let key = format!("seat:{}", body.country);
+                let claimed = storage.get::<bool>(&key).await.is_err();
+                if claimed {
+                    self.state.storage().put(&key, true).await?;
+                }
+                Response::from_json(&Resp { claimed })
+            }
+            "/create_conversation" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    conversation_id: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    created: bool,
+                }
+
+                let body: Body = req.json().await?;
+                let storage = self.state.storage();
+                let key = format!("conv:{}", body.conversation_id);
+                let created = storage.get::<bool>(&key).await.is_err();
+                if created {
+                    self.state.storage().put(&key, true).await?;
+                }
+                Response::from_json(&Resp { created })
+            }
+            "/next_seq" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    conversation_id: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    seq: u64,
+                }
+
+                let body: Body = req.json().await?;
+                let storage = self.state.storage();
+                let key = format!("seq:{}", body.conversation_id);
+                let next = storage.get::<u64>(&key).await.unwrap_or(0) + 1;
+                self.state.storage().put(&key, next).await?;
+                Response::from_json(&Resp { seq: next })
+            }
+            // Compensating actions: callers use these to undo a claim/create
+            // decision when the KV mirror write that was supposed to follow
+            // it fails, so a retry doesn't find the seat/conversation
+            // permanently stuck in the "already claimed" state.
+            "/release_seat" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    country: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    released: bool,
+                }
+
+                let body: Body = req.json().await?;
+                let released = self
+                    .state
+                    .storage()
+                    .delete(&format!("seat:{}", body.country))
+                    .await?;
+                Response::from_json(&Resp { released })
+            }
+            "/append_conversation" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    conversation_id: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    conversations: Vec<String>,
+                }
+
+                let body: Body = req.json().await?;
+                let storage = self.state.storage();
+                let mut ids: Vec<String> =
+                    storage.get::<Vec<String>>("conversations").await.unwrap_or_default();
+                if !ids.contains(&body.conversation_id) {
+                    ids.push(body.conversation_id);
+                    self.state.storage().put("conversations", &ids).await?;
+                }
+                Response::from_json(&Resp { conversations: ids })
+            }
+            "/release_conversation" => {
+                #[derive(Deserialize)]
+                struct Body {
+                    conversation_id: String,
+                }
+                #[derive(Serialize)]
+                struct Resp {
+                    released: bool,
+                }
+
+                let body: Body = req.json().await?;
+                let released = self
+                    .state
+                    .storage()
+                    .delete(&format!("conv:{}", body.conversation_id))
+                    .await?;
+                Response::from_json(&Resp { released })
+            }
+            _ => Response::error("Not Found", 404),
+        }
+    }
+}
+
+// Calls into the room's Durable Object and decodes its JSON response. All
+// room-scoped atomicity (seat claims, conversation creation, message
+// sequencing) is funneled through this single stub per room_id.
+async fn call_room_do<B: Serialize, T: for<'de> Deserialize<'de>>(
+    env: &Env,
+    room_id: &str,
+    path: &str,
+    body: &B,
+) -> Result<T> {
+    let namespace = env.durable_object(ROOM_DO_BINDING)?;
+    let stub = namespace.id_from_name(room_id)?.get_stub()?;
+
+    let json = serde_json::to_string(body).map_err(|e| Error::RustError(e.to_string()))?;
+    let mut init = RequestInit::new();
+    init.method = Method::Post;
+    init.body = Some(JsValue::from_str(&json));
+
+    let do_req = Request::new_with_init(&format!("https://room-do{}", path), &init)?;
+    let mut resp = stub.fetch_with_request(do_req).await?;
+    resp.json::<T>().await
+}
+
+// Undoes a `/claim_seat` decision. Used when the KV mirror write that's
+// supposed to follow a successful claim fails, so the seat doesn't end up
+// permanently (and silently) claimed with no token ever issued for it.
+async fn release_seat(env: &Env, room_id: &str, country: &str) -> Result<()> {
+    #[derive(Serialize)]
+    struct Req<'a> {
+        country: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct Resp {
+        #[allow(dead_code)]
+        released: bool,
+    }
+    call_room_do::<_, Resp>(env, room_id, "/release_seat", &Req { country }).await?;
+    Ok(())
+}
+
+// Undoes a `/create_conversation` decision, for the same reason as
+// `release_seat` above.
+async fn release_conversation(env: &Env, room_id: &str, conversation_id: &str) -> Result<()> {
+    #[derive(Serialize)]
+    struct Req<'a> {
+        conversation_id: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct Resp {
+        #[allow(dead_code)]
+        released: bool,
+    }
+    call_room_do::<_, Resp>(env, room_id, "/release_conversation", &Req { conversation_id }).await?;
+    Ok(())
+}
+
 // ==================== Data types ====================
 
 #[derive(Serialize, Deserialize)]
@@ -110,12 +350,61 @@ struct Message {
     sender_country: String,
     content: String,
     timestamp: u64,
+    // Monotonically increasing per-conversation order, allocated by the
+    // conversation's RoomDurableObject. Independent of `timestamp` so two
+    // messages posted in the same millisecond still sort deterministically.
+    seq: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    redacted: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    edited_at: Option<u64>,
 }
 
 #[derive(Serialize)]
 struct ConversationInfo {
     conversation_id: String,
     participants: Vec<String>,
+    unread_count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Presence {
+    last_seen: u64,
+    status: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CountryStatus {
+    country: String,
+    claimed: bool,
+    online: bool,
+    status: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Pusher {
+    callback_url: String,
+    secret: String,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload {
+    conversation_id: String,
+    sender_country: String,
+    timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Report {
+    report_id: String,
+    room_id: String,
+    conversation_id: String,
+    message_id: String,
+    reporter_country: String,
+    target_content: String,
+    reason: Option<String>,
+    severity: u8,
+    timestamp: u64,
 }
 
 // ==================== CORS ====================
@@ -148,6 +437,11 @@ async fn handle_auth(mut req: Request, env: &Env) -> Result<Response> {
     struct Body {
         room_id: String,
         country: String,
+        // Opt-in organizer escalation: if this matches the ORGANIZER_SECRET
+        // binding, the minted token also carries `is_organizer`. Anyone who
+        // doesn't supply it (or gets it wrong) authenticates as a normal
+        // seated country, same as before this field existed.
+        organizer_secret: Option<String>,
     }
 
     let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
@@ -159,23 +453,51 @@ async fn handle_auth(mut req: Request, env: &Env) -> Result<Response> {
         return err("Invalid room_id", 400);
     }
 
+    let is_organizer = match &body.organizer_secret {
+        Some(candidate) => env
+            .secret(ORGANIZER_SECRET_BINDING)
+            .map(|configured| configured.to_string() == *candidate)
+            .unwrap_or(false),
+        None => false,
+    };
+
     let kv = env.kv(KV_BINDING)?;
     let seat_key = format!("room:{}:seat:{}", body.room_id, body.country);
 
     // Once a seat is claimed, no new token can be issued for that room+country.
-    // Note: KV does not support atomic check-and-set, so a very narrow race
-    // condition exists where two simultaneous first-time requests could both
-    // pass this check. In practice this is negligible for a diplomacy game.
-    let taken: Option<bool> = kv_get(&kv, &seat_key).await?;
-    if taken.is_some() {
+    // The room's Durable Object is the serialization point for this decision,
+    // so two simultaneous first-time requests can't both win the claim.
+    #[derive(Serialize)]
+    struct ClaimSeatReq<'a> {
+        country: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct ClaimSeatResp {
+        claimed: bool,
+    }
+
+    let claim: ClaimSeatResp = call_room_do(
+        env,
+        &body.room_id,
+        "/claim_seat",
+        &ClaimSeatReq { country: &body.country },
+    )
+    .await?;
+    if !claim.claimed {
         return err("Seat already taken", 409);
     }
 
     let secret = env.secret(SECRET_BINDING)?.to_string();
-    let access_token = make_token(&secret, &body.room_id, &body.country);
-
-    // Mark this seat as claimed before returning the token.
-    kv_put(&kv, &seat_key, &true).await?;
+    let access_token = make_token(&secret, &body.room_id, &body.country, is_organizer);
+
+    // Mirror the claim in KV so reads like the room roster don't need the DO.
+    // If this fails, release the DO's claim so the seat isn't bricked —
+    // otherwise a retry would see `claimed: false` forever with no token
+    // ever having been issued.
+    if let Err(e) = kv_put(&kv, &seat_key, &true).await {
+        let _ = release_seat(env, &body.room_id, &body.country).await;
+        return Err(e);
+    }
 
     #[derive(Serialize)]
     struct Resp {
@@ -209,9 +531,14 @@ async fn handle_get_conversations(req: Request, env: &Env) -> Result<Response> {
     for id in conv_ids {
         if let Some(meta) = kv_get::<ConvMeta>(&kv, &format!("conv:{}:meta", id)).await? {
             if meta.participants.contains(&claims.country) {
+                let read_marker: u64 = kv_get(&kv, &format!("conv:{}:read:{}", id, claims.country))
+                    .await?
+                    .unwrap_or(0);
+                let unread_count = count_unread(&kv, &id, read_marker).await?;
                 result.push(ConversationInfo {
                     conversation_id: id,
                     participants: meta.participants,
+                    unread_count,
                 });
             }
         }
@@ -220,6 +547,49 @@ async fn handle_get_conversations(req: Request, env: &Env) -> Result<Response> {
     with_cors(Response::from_json(&result)?)
 }
 
+// Writes a newly created conversation's meta and appends it to its room's
+// conversation list. Split out so `handle_post_conversations` can release
+// the DO's creation decision if any of these writes fails.
+async fn persist_new_conversation(
+    env: &Env,
+    kv: &kv::KvStore,
+    room_id: &str,
+    conv_id: &str,
+    participants: &[String],
+) -> Result<()> {
+    let mut sorted_participants = participants.to_vec();
+    sorted_participants.sort();
+    let meta = ConvMeta {
+        room_id: room_id.to_string(),
+        participants: sorted_participants,
+    };
+    kv_put(kv, &format!("conv:{}:meta", conv_id), &meta).await?;
+
+    // The room's conversation list is shared across every conversation in
+    // the room, so its read-modify-write also goes through the DO — two
+    // conversations created concurrently in the same room must not clobber
+    // each other's append the way a plain KV read-modify-write would.
+    #[derive(Serialize)]
+    struct AppendConversationReq<'a> {
+        conversation_id: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct AppendConversationResp {
+        conversations: Vec<String>,
+    }
+
+    let result: AppendConversationResp = call_room_do(
+        env,
+        room_id,
+        "/append_conversation",
+        &AppendConversationReq { conversation_id: conv_id },
+    )
+    .await?;
+
+    let list_key = format!("room:{}:conversations", room_id);
+    kv_put(kv, &list_key, &result.conversations).await
+}
+
 // ==================== POST /api/conversations ====================
 
 async fn handle_post_conversations(mut req: Request, env: &Env) -> Result<Response> {
@@ -261,23 +631,35 @@ async fn handle_post_conversations(mut req: Request, env: &Env) -> Result<Respon
 
     let conv_id = conversation_id(&claims.room_id, &body.participants);
     let kv = env.kv(KV_BINDING)?;
-    let meta_key = format!("conv:{}:meta", conv_id);
-
-    if kv_get::<ConvMeta>(&kv, &meta_key).await?.is_none() {
-        let mut sorted_participants = body.participants.clone();
-        sorted_participants.sort();
-        let meta = ConvMeta {
-            room_id: claims.room_id.clone(),
-            participants: sorted_participants,
-        };
-        kv_put(&kv, &meta_key, &meta).await?;
-
-        // Add conversation to room list
-        let list_key = format!("room:{}:conversations", claims.room_id);
-        let mut ids: Vec<String> = kv_get(&kv, &list_key).await?.unwrap_or_default();
-        if !ids.contains(&conv_id) {
-            ids.push(conv_id.clone());
-            kv_put(&kv, &list_key, &ids).await?;
+
+    // The room's Durable Object decides, exactly once, which caller is first
+    // to create this conversation — avoiding the read-modify-write race a
+    // plain KV existence check would have on the room's conversation list.
+    #[derive(Serialize)]
+    struct CreateConversationReq<'a> {
+        conversation_id: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct CreateConversationResp {
+        created: bool,
+    }
+
+    let result: CreateConversationResp = call_room_do(
+        env,
+        &claims.room_id,
+        "/create_conversation",
+        &CreateConversationReq { conversation_id: &conv_id },
+    )
+    .await?;
+
+    if result.created {
+        // If persisting the meta/room-list mirror fails, release the DO's
+        // decision so a retry can create this conversation again — otherwise
+        // it would be permanently stuck "created" with no ConvMeta ever
+        // stored.
+        if let Err(e) = persist_new_conversation(env, &kv, &claims.room_id, &conv_id, &body.participants).await {
+            let _ = release_conversation(env, &claims.room_id, &conv_id).await;
+            return Err(e);
         }
     }
 
@@ -303,10 +685,16 @@ async fn handle_get_messages(req: Request, env: &Env) -> Result<Response> {
         Some(id) => id.to_string(),
         None => return err("Missing conversation_id", 400),
     };
+    // `since` and `before` are conversation-local seqs (allocated by the
+    // RoomDurableObject), not wall-clock timestamps — it's what the message
+    // key is ordered by. `since` pages forward (exclusive lower bound),
+    // `before` pages backward (exclusive upper bound, e.g. from the `start`
+    // token returned by `handle_get_messages_context`).
     let since: u64 = params
         .get("since")
         .and_then(|s| s.parse::<u64>().ok())
         .unwrap_or(0);
+    let before: Option<u64> = params.get("before").and_then(|s| s.parse::<u64>().ok());
 
     let kv = env.kv(KV_BINDING)?;
 
@@ -320,32 +708,127 @@ async fn handle_get_messages(req: Request, env: &Env) -> Result<Response> {
     }
 
     // List all message keys for this conversation; keys are lexicographically sortable
-    // due to zero-padded timestamp prefix
+    // due to the zero-padded seq prefix
     let prefix = format!("conv:{}:msg:", conv_id);
     let list_result = kv.list().prefix(prefix.clone()).execute().await?;
 
-    let since_key = format!("conv:{}:msg:{:020}:", conv_id, since);
     let mut messages: Vec<Message> = Vec::new();
 
-    for key_info in list_result.keys {
-        // Skip keys at or before the since timestamp
-        if since > 0 && key_info.name <= since_key {
-            continue;
+    if let Some(before) = before {
+        // Walk backward from the newest key so we return the messages
+        // immediately preceding `before`, not the oldest ones in the
+        // conversation.
+        for key_info in list_result.keys.iter().rev() {
+            let matches = seq_from_key(&prefix, &key_info.name)
+                .map(|seq| seq < before && seq > since)
+                .unwrap_or(false);
+            if !matches {
+                continue;
+            }
+            if let Some(msg) = kv_get::<Message>(&kv, &key_info.name).await? {
+                messages.push(msg);
+            }
+            if messages.len() >= MAX_MSG_FETCH {
+                break;
+            }
+        }
+        messages.reverse();
+    } else {
+        for key_info in &list_result.keys {
+            let matches = seq_from_key(&prefix, &key_info.name)
+                .map(|seq| seq > since)
+                .unwrap_or(false);
+            if !matches {
+                continue;
+            }
+            if let Some(msg) = kv_get::<Message>(&kv, &key_info.name).await? {
+                messages.push(msg);
+            }
+            if messages.len() >= MAX_MSG_FETCH {
+                break;
+            }
         }
+    }
+
+    with_cors(Response::from_json(&messages)?)
+}
+
+// ==================== GET /api/messages/context ====================
+
+async fn handle_get_messages_context(req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    let url = req.url()?;
+    let params: HashMap<_, _> = url.query_pairs().collect();
+    let conv_id = match params.get("conversation_id") {
+        Some(id) => id.to_string(),
+        None => return err("Missing conversation_id", 400),
+    };
+    let message_id = match params.get("message_id") {
+        Some(id) => id.to_string(),
+        None => return err("Missing message_id", 400),
+    };
+    let limit: usize = params
+        .get("limit")
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_CONTEXT_LIMIT)
+        .min(MAX_MSG_FETCH);
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", conv_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    // Keys are lexicographically ordered by zero-padded seq, so the
+    // target's position in this list gives us a window directly.
+    let prefix = format!("conv:{}:msg:", conv_id);
+    let list_result = kv.list().prefix(prefix).execute().await?;
+    let id_suffix = format!(":{}", message_id);
+
+    let target_idx = list_result
+        .keys
+        .iter()
+        .position(|key_info| key_info.name.ends_with(&id_suffix));
+    let target_idx = match target_idx {
+        Some(idx) => idx,
+        None => return err("Message not found", 404),
+    };
+
+    let start_idx = target_idx.saturating_sub(limit);
+    let end_idx = (target_idx + limit + 1).min(list_result.keys.len());
+
+    let mut messages: Vec<Message> = Vec::new();
+    for key_info in &list_result.keys[start_idx..end_idx] {
         if let Some(msg) = kv_get::<Message>(&kv, &key_info.name).await? {
             messages.push(msg);
         }
-        if messages.len() >= MAX_MSG_FETCH {
-            break;
-        }
     }
 
-    with_cors(Response::from_json(&messages)?)
+    #[derive(Serialize)]
+    struct Resp {
+        messages: Vec<Message>,
+        start: u64,
+        end: u64,
+    }
+    let start = messages.first().map(|m| m.seq).unwrap_or(0);
+    let end = messages.last().map(|m| m.seq).unwrap_or(0);
+    with_cors(Response::from_json(&Resp { messages, start, end })?)
 }
 
 // ==================== POST /api/messages ====================
 
-async fn handle_post_messages(mut req: Request, env: &Env) -> Result<Response> {
+async fn handle_post_messages(mut req: Request, env: &Env, ctx: &Context) -> Result<Response> {
     let secret = env.secret(SECRET_BINDING)?.to_string();
     let claims = match get_claims(&secret, &req) {
         Some(c) => c,
@@ -378,6 +861,26 @@ async fn handle_post_messages(mut req: Request, env: &Env) -> Result<Response> {
     let timestamp = Date::now().as_millis() as u64;
     let message_id = uuid::Uuid::new_v4().to_string();
 
+    // Allocate this conversation's next seq through its room's Durable
+    // Object, so two messages posted in the same millisecond still get a
+    // deterministic order independent of wall-clock collisions.
+    #[derive(Serialize)]
+    struct NextSeqReq<'a> {
+        conversation_id: &'a str,
+    }
+    #[derive(Deserialize)]
+    struct NextSeqResp {
+        seq: u64,
+    }
+
+    let next_seq: NextSeqResp = call_room_do(
+        env,
+        &claims.room_id,
+        "/next_seq",
+        &NextSeqReq { conversation_id: &body.conversation_id },
+    )
+    .await?;
+
     let message = Message {
         message_id: message_id.clone(),
         room_id: claims.room_id.clone(),
@@ -385,14 +888,35 @@ async fn handle_post_messages(mut req: Request, env: &Env) -> Result<Response> {
         sender_country: claims.country.clone(),
         content: body.content,
         timestamp,
+        seq: next_seq.seq,
+        redacted: None,
+        edited_at: None,
     };
 
     let msg_key = format!(
         "conv:{}:msg:{:020}:{}",
-        body.conversation_id, timestamp, message_id
+        body.conversation_id, next_seq.seq, message_id
     );
     kv_put(&kv, &msg_key, &message).await?;
 
+    for recipient in &meta.participants {
+        if recipient == &claims.country {
+            continue;
+        }
+        let pusher_key = format!("room:{}:pusher:{}", claims.room_id, recipient);
+        if let Some(pusher) = kv_get::<Pusher>(&kv, &pusher_key).await? {
+            dispatch_webhook(
+                ctx,
+                pusher,
+                WebhookPayload {
+                    conversation_id: body.conversation_id.clone(),
+                    sender_country: claims.country.clone(),
+                    timestamp,
+                },
+            );
+        }
+    }
+
     #[derive(Serialize)]
     struct Resp {
         message_id: String,
@@ -400,10 +924,498 @@ async fn handle_post_messages(mut req: Request, env: &Env) -> Result<Response> {
     with_cors(Response::from_json(&Resp { message_id })?)
 }
 
+// ==================== POST /api/messages/redact ====================
+
+async fn handle_post_messages_redact(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        conversation_id: String,
+        message_id: String,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", body.conversation_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    let (key, mut message) = match find_message_entry(&kv, &body.conversation_id, &body.message_id).await? {
+        Some(entry) => entry,
+        None => return err("Message not found", 404),
+    };
+
+    if message.sender_country != claims.country {
+        return err("Forbidden", 403);
+    }
+
+    // Tombstone in place rather than deleting the key, so `since` pagination
+    // and seq ordering in `handle_get_messages` stay intact.
+    message.content = "[redacted]".to_string();
+    message.redacted = Some(true);
+    kv_put(&kv, &key, &message).await?;
+
+    with_cors(Response::ok("ok")?)
+}
+
+// ==================== POST /api/messages/edit ====================
+
+async fn handle_post_messages_edit(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        conversation_id: String,
+        message_id: String,
+        content: String,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    if body.content.is_empty() || body.content.len() > MAX_CONTENT_LENGTH {
+        return err("content must be 1–4096 bytes", 400);
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", body.conversation_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    let (key, mut message) = match find_message_entry(&kv, &body.conversation_id, &body.message_id).await? {
+        Some(entry) => entry,
+        None => return err("Message not found", 404),
+    };
+
+    if message.sender_country != claims.country {
+        return err("Forbidden", 403);
+    }
+    if message.redacted == Some(true) {
+        return err("Message has been redacted", 409);
+    }
+
+    message.content = body.content;
+    message.edited_at = Some(Date::now().as_millis() as u64);
+    kv_put(&kv, &key, &message).await?;
+
+    with_cors(Response::ok("ok")?)
+}
+
+// ==================== Pushers ====================
+
+// Fires a signed webhook to a registered pusher without blocking the caller.
+// `ctx.wait_until` keeps the Worker alive long enough for the fetch to
+// complete after the response has already been returned.
+fn dispatch_webhook(ctx: &Context, pusher: Pusher, payload: WebhookPayload) {
+    ctx.wait_until(async move {
+        let body = match serde_json::to_string(&payload) {
+            Ok(b) => b,
+            Err(_) => return,
+        };
+        let signature = sign_payload(&pusher.secret, &body);
+
+        let mut headers = Headers::new();
+        if headers.set("Content-Type", "application/json").is_err() {
+            return;
+        }
+        if headers.set("X-Council-Signature", &signature).is_err() {
+            return;
+        }
+
+        let mut init = RequestInit::new();
+        init.method = Method::Post;
+        init.headers = headers;
+        init.body = Some(JsValue::from_str(&body));
+
+        let webhook_req = match Request::new_with_init(&pusher.callback_url, &init) {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+
+        // Best-effort delivery: failures aren't surfaced back to the sender.
+        let _ = Fetch::Request(webhook_req).send().await;
+    });
+}
+
+// ==================== POST /api/pushers ====================
+
+async fn handle_post_pushers(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        callback_url: String,
+        secret: String,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    if !body.callback_url.starts_with("https://") {
+        return err("callback_url must be https", 400);
+    }
+    if body.secret.is_empty() {
+        return err("secret must not be empty", 400);
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+    let pusher_key = format!("room:{}:pusher:{}", claims.room_id, claims.country);
+    let pusher = Pusher {
+        callback_url: body.callback_url,
+        secret: body.secret,
+    };
+    kv_put(&kv, &pusher_key, &pusher).await?;
+
+    with_cors(Response::ok("ok")?)
+}
+
+// ==================== Read markers ====================
+
+// Counts message keys for a conversation sorting after the given read marker
+// (a seq, same as `since` in `handle_get_messages`). Mirrors its parsed-seq
+// comparison, but only counts keys instead of fetching each message.
+async fn count_unread(kv: &kv::KvStore, conv_id: &str, read_marker: u64) -> Result<u64> {
+    let prefix = format!("conv:{}:msg:", conv_id);
+    let list_result = kv.list().prefix(prefix.clone()).execute().await?;
+
+    let mut count = 0u64;
+    for key_info in list_result.keys {
+        if seq_from_key(&prefix, &key_info.name).map(|s| s > read_marker).unwrap_or(false) {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// ==================== POST /api/read_markers ====================
+
+async fn handle_post_read_markers(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        conversation_id: String,
+        // Renamed from `last_read_timestamp` (the original chunk0-2 field)
+        // once message ordering became seq-based rather than wall-clock —
+        // `serde(alias)` keeps accepting the original field name so clients
+        // built against the original contract don't silently break.
+        #[serde(alias = "last_read_timestamp")]
+        last_read_seq: u64,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", body.conversation_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    let read_key = format!("conv:{}:read:{}", body.conversation_id, claims.country);
+    kv_put(&kv, &read_key, &body.last_read_seq).await?;
+
+    with_cors(Response::ok("ok")?)
+}
+
+// ==================== GET /api/read_markers ====================
+
+async fn handle_get_read_markers(req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    let url = req.url()?;
+    let params: HashMap<_, _> = url.query_pairs().collect();
+    let conv_id = match params.get("conversation_id") {
+        Some(id) => id.to_string(),
+        None => return err("Missing conversation_id", 400),
+    };
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", conv_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    let mut markers: HashMap<String, u64> = HashMap::new();
+    for country in &meta.participants {
+        let read_key = format!("conv:{}:read:{}", conv_id, country);
+        if let Some(ts) = kv_get::<u64>(&kv, &read_key).await? {
+            markers.insert(country.clone(), ts);
+        }
+    }
+
+    with_cors(Response::from_json(&markers)?)
+}
+
+// ==================== Reports ====================
+
+// Scans a conversation's message keys for the one matching `message_id`.
+// There's no secondary index from message_id to its seq-prefixed key,
+// so this walks the (lexicographically ordered) prefix until it finds it.
+async fn find_message_entry(
+    kv: &kv::KvStore,
+    conv_id: &str,
+    message_id: &str,
+) -> Result<Option<(String, Message)>> {
+    let prefix = format!("conv:{}:msg:", conv_id);
+    let list_result = kv.list().prefix(prefix).execute().await?;
+    for key_info in list_result.keys {
+        if let Some(msg) = kv_get::<Message>(&kv, &key_info.name).await? {
+            if msg.message_id == message_id {
+                return Ok(Some((key_info.name, msg)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+async fn find_message(kv: &kv::KvStore, conv_id: &str, message_id: &str) -> Result<Option<Message>> {
+    Ok(find_message_entry(kv, conv_id, message_id).await?.map(|(_, msg)| msg))
+}
+
+// ==================== POST /api/reports ====================
+
+async fn handle_post_reports(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        conversation_id: String,
+        message_id: String,
+        reason: Option<String>,
+        severity: u8,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    if body.severity < MIN_SEVERITY || body.severity > MAX_SEVERITY {
+        return err("severity must be 1–5", 400);
+    }
+    if let Some(reason) = &body.reason {
+        if reason.len() > MAX_REASON_LENGTH {
+            return err("reason too long", 400);
+        }
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+
+    let meta: ConvMeta = match kv_get(&kv, &format!("conv:{}:meta", body.conversation_id)).await? {
+        Some(m) => m,
+        None => return err("Conversation not found", 404),
+    };
+
+    if meta.room_id != claims.room_id || !meta.participants.contains(&claims.country) {
+        return err("Forbidden", 403);
+    }
+
+    let message = match find_message(&kv, &body.conversation_id, &body.message_id).await? {
+        Some(m) => m,
+        None => return err("Message not found", 404),
+    };
+
+    let timestamp = Date::now().as_millis() as u64;
+    let report_id = uuid::Uuid::new_v4().to_string();
+
+    let report = Report {
+        report_id: report_id.clone(),
+        room_id: claims.room_id.clone(),
+        conversation_id: body.conversation_id,
+        message_id: body.message_id,
+        reporter_country: claims.country,
+        target_content: message.content,
+        reason: body.reason,
+        severity: body.severity,
+        timestamp,
+    };
+
+    let report_key = format!(
+        "room:{}:reports:{:020}:{}",
+        claims.room_id, timestamp, report_id
+    );
+    kv_put(&kv, &report_key, &report).await?;
+
+    #[derive(Serialize)]
+    struct Resp {
+        report_id: String,
+    }
+    with_cors(Response::from_json(&Resp { report_id })?)
+}
+
+// ==================== GET /api/reports ====================
+
+async fn handle_get_reports(req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    let url = req.url()?;
+    let params: HashMap<_, _> = url.query_pairs().collect();
+    let room_id = params.get("room_id").map(|v| v.as_ref()).unwrap_or("");
+    if room_id != claims.room_id {
+        return err("Unauthorized", 401);
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+    let prefix = format!("room:{}:reports:", claims.room_id);
+    let list_result = kv.list().prefix(prefix).execute().await?;
+
+    // Reports carry a plaintext snapshot of the flagged message. An
+    // organizer (a token minted with the ORGANIZER_SECRET) sees every
+    // report in the room, matching the moderation role the backlog asked
+    // for; everyone else only sees reports from conversations they
+    // actually participate in.
+    let mut participant_cache: HashMap<String, bool> = HashMap::new();
+    let mut reports: Vec<Report> = Vec::new();
+    for key_info in list_result.keys {
+        if let Some(report) = kv_get::<Report>(&kv, &key_info.name).await? {
+            let is_participant = claims.is_organizer || match participant_cache.get(&report.conversation_id) {
+                Some(cached) => *cached,
+                None => {
+                    let meta: Option<ConvMeta> =
+                        kv_get(&kv, &format!("conv:{}:meta", report.conversation_id)).await?;
+                    let is_participant = meta
+                        .map(|m| m.participants.contains(&claims.country))
+                        .unwrap_or(false);
+                    participant_cache.insert(report.conversation_id.clone(), is_participant);
+                    is_participant
+                }
+            };
+            if is_participant {
+                reports.push(report);
+            }
+        }
+    }
+
+    with_cors(Response::from_json(&reports)?)
+}
+
+// ==================== POST /api/presence ====================
+
+async fn handle_post_presence(mut req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    #[derive(Deserialize)]
+    struct Body {
+        status: Option<String>,
+    }
+
+    let body: Body = req.json().await.map_err(|_| Error::RustError("Invalid JSON".into()))?;
+
+    if let Some(status) = &body.status {
+        if status.len() > 128 {
+            return err("status too long", 400);
+        }
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+    let presence_key = format!("room:{}:presence:{}", claims.room_id, claims.country);
+    let presence = Presence {
+        last_seen: Date::now().as_millis() as u64,
+        status: body.status,
+    };
+    kv_put(&kv, &presence_key, &presence).await?;
+
+    with_cors(Response::ok("ok")?)
+}
+
+// ==================== GET /api/room ====================
+
+async fn handle_get_room(req: Request, env: &Env) -> Result<Response> {
+    let secret = env.secret(SECRET_BINDING)?.to_string();
+    let claims = match get_claims(&secret, &req) {
+        Some(c) => c,
+        None => return err("Unauthorized", 401),
+    };
+
+    let url = req.url()?;
+    let params: HashMap<_, _> = url.query_pairs().collect();
+    let room_id = params.get("room_id").map(|v| v.as_ref()).unwrap_or("");
+    if room_id != claims.room_id {
+        return err("Unauthorized", 401);
+    }
+
+    let kv = env.kv(KV_BINDING)?;
+    let now = Date::now().as_millis() as u64;
+
+    let mut roster: Vec<CountryStatus> = Vec::new();
+    for country in COUNTRIES {
+        let seat_key = format!("room:{}:seat:{}", claims.room_id, country);
+        let claimed = kv_get::<bool>(&kv, &seat_key).await?.is_some();
+
+        let presence_key = format!("room:{}:presence:{}", claims.room_id, country);
+        let presence = kv_get::<Presence>(&kv, &presence_key).await?;
+        let online = presence
+            .as_ref()
+            .map(|p| now.saturating_sub(p.last_seen) <= PRESENCE_ONLINE_WINDOW_MS)
+            .unwrap_or(false);
+        let status = presence.and_then(|p| p.status);
+
+        roster.push(CountryStatus {
+            country: country.to_string(),
+            claimed,
+            online,
+            status,
+        });
+    }
+
+    with_cors(Response::from_json(&roster)?)
+}
+
 // ==================== Entry point ====================
 
 #[event(fetch)]
-pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
+pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
     if req.method() == Method::Options {
         return cors_preflight();
     }
@@ -414,7 +1426,17 @@ pub async fn main(req: Request, env: Env, _ctx: Context) -> Result<Response> {
         (Method::Get, "/api/conversations") => handle_get_conversations(req, &env).await,
         (Method::Post, "/api/conversations") => handle_post_conversations(req, &env).await,
         (Method::Get, "/api/messages") => handle_get_messages(req, &env).await,
-        (Method::Post, "/api/messages") => handle_post_messages(req, &env).await,
+        (Method::Get, "/api/messages/context") => handle_get_messages_context(req, &env).await,
+        (Method::Post, "/api/messages") => handle_post_messages(req, &env, &ctx).await,
+        (Method::Post, "/api/messages/redact") => handle_post_messages_redact(req, &env).await,
+        (Method::Post, "/api/messages/edit") => handle_post_messages_edit(req, &env).await,
+        (Method::Post, "/api/pushers") => handle_post_pushers(req, &env).await,
+        (Method::Get, "/api/room") => handle_get_room(req, &env).await,
+        (Method::Post, "/api/presence") => handle_post_presence(req, &env).await,
+        (Method::Post, "/api/read_markers") => handle_post_read_markers(req, &env).await,
+        (Method::Get, "/api/read_markers") => handle_get_read_markers(req, &env).await,
+        (Method::Post, "/api/reports") => handle_post_reports(req, &env).await,
+        (Method::Get, "/api/reports") => handle_get_reports(req, &env).await,
         (Method::Get, "/api/health") => with_cors(Response::ok("ok")?),
         _ => err("Not Found", 404),
     }